@@ -1,24 +1,82 @@
 //! Library for parsing escape characters
 
+use std::ops::Range;
+
+/// Why a backslash escape sequence in the input could not be decoded, returned alongside the
+/// byte offset in the input at which the offending escape begins.
+///
+/// Modeled on the errors produced by rustc's own literal-unescaping lexer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EscapeError {
+    /// A trailing backslash was not followed by anything.
+    LoneSlash,
+    /// The character following a backslash does not start a recognized escape.
+    InvalidEscape,
+    /// A `\xNN` escape did not have two hex digits before the input ended.
+    TooShortHexEscape,
+    /// A `\xNN` escape contained a non-hex-digit character.
+    InvalidCharInHexEscape,
+    /// A `\xNN` escape's value is out of range for the current mode.
+    OutOfRangeHexEscape,
+    /// A `\u` escape was not followed by `{`.
+    NoBraceInUnicodeEscape,
+    /// A `\u{}` escape had no digits between its braces.
+    EmptyUnicodeEscape,
+    /// A `\u{...}` escape was missing its closing `}`.
+    UnclosedUnicodeEscape,
+    /// A `\u{...}` escape had more than six hex digits.
+    OverlongUnicodeEscape,
+    /// A `\u{...}` escape decoded to a surrogate code point.
+    LoneSurrogateUnicodeEscape,
+    /// A `\u{...}` escape decoded to a value greater than `0x10FFFF`.
+    OutOfRangeUnicodeEscape,
+    /// A `\u{...}` escape was used in a mode that only supports byte-sized escapes.
+    UnicodeEscapeInByte,
+    /// The decoded bytes were not valid UTF-8.
+    NonUtf8(std::string::FromUtf8Error),
+}
+
+/// Which family of escape sequences [`escape_with`] should accept.
+///
+/// Mirrors how rustc's own lexer shares one unescaping routine across char, string, byte, and
+/// byte-string literals, differing only in which escapes are legal and how far a `\xNN` escape
+/// may range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// [ASCII escapes](https://doc.rust-lang.org/reference/tokens.html#ascii-escapes): `\xNN` is capped at `0x7F`; no `\u{...}`.
+    Ascii,
+    /// [Byte escapes](https://doc.rust-lang.org/reference/tokens.html#byte-escapes): `\xNN` may go up to `0xFF`; no `\u{...}`.
+    Byte,
+    /// [Unicode escapes](https://doc.rust-lang.org/reference/tokens.html#unicode-escapes): `\xNN` is capped at `0x7F`; `\u{...}` is permitted.
+    Unicode,
+    /// All escapes legal inside a char literal: `\xNN` capped at `0x7F`, and `\u{...}` permitted.
+    Char,
+}
+
+impl Mode {
+    fn allows_unicode_escape(self) -> bool {
+        matches!(self, Mode::Unicode | Mode::Char)
+    }
+
+    fn max_hex_escape(self) -> u8 {
+        match self {
+            Mode::Byte => 0xFF,
+            Mode::Ascii | Mode::Unicode | Mode::Char => 0x7F,
+        }
+    }
+}
+
 /// Escape [ASCII escapes](https://doc.rust-lang.org/reference/tokens.html#ascii-escapes) in `input`
 ///
 /// Turns sequences that look like escape characters into actual escape characters, i.e. a
 /// backslash followed by an 'n' turns into a proper newline character.
 /// The only difference between ASCII escapes and Byte escapes is that the maximum value for a hex
 /// escape in `escape_ascii` is 0x7F.
-pub fn escape_ascii(input: &str) -> Result<String, std::string::FromUtf8Error> {
-    if input.len() < 1 {
-        return Ok(String::new());
-    }
-
-    let mut v = Vec::from(input);
-    for i in 0..(v.len() - 1) {
-        if v[i] == '\\' as u8 && is_escapable(v[i + 1] as char) {
-            v.remove(i);
-            v[i] = char_to_escape_sequence(v[i] as char) as u8;
-        }
-    }
-    String::from_utf8(v)
+///
+/// On failure, returns the byte offset of the escape sequence that could not be decoded alongside
+/// the reason.
+pub fn escape_ascii(input: &str) -> Result<String, (usize, EscapeError)> {
+    escape_with(input, Mode::Ascii)
 }
 
 /// Escape [Byte escapes](https://doc.rust-lang.org/reference/tokens.html#byte-escapes) in `input`
@@ -28,22 +86,268 @@ pub fn escape_ascii(input: &str) -> Result<String, std::string::FromUtf8Error> {
 ///
 /// The only difference between Byte escapes and ASCII escapes is that the maximum value for a hex
 /// escape in `escape_bytes` is 0xFF.
-pub fn escape_bytes(input: &str) -> Result<String, std::string::FromUtf8Error> {
-    escape_ascii(input)
+pub fn escape_bytes(input: &str) -> Result<String, (usize, EscapeError)> {
+    escape_with(input, Mode::Byte)
 }
 
 /// Escape [Unicode escapes](https://doc.rust-lang.org/reference/tokens.html#unicode-escapes) in
 /// `input`
-pub fn escape_unicode(_input: &str) -> Result<String, std::string::FromUtf8Error> {
-    unimplemented!("`escape_unicode` is not yet implemented");
+///
+/// A unicode escape is a backslash, a `u`, an opening brace, one to six hex digits (`_`
+/// separators are allowed between digits, as rustc allows), and a closing brace, e.g.
+/// `\u{7fff}`. The resulting code point is rejected if it is greater than `0x10FFFF` or falls in
+/// the surrogate range `0xD800..=0xDFFF`.
+pub fn escape_unicode(input: &str) -> Result<String, (usize, EscapeError)> {
+    escape_with(input, Mode::Unicode)
+}
+
+/// Core routine shared by [`escape_ascii`], [`escape_bytes`], and [`escape_unicode`].
+///
+/// Scans `input` once, decoding each backslash escape legal under `mode` and copying every other
+/// byte through unchanged, into a freshly built buffer (the prior in-place `Vec<u8>` could not
+/// represent multi-byte unicode escapes).
+fn escape_with(input: &str, mode: Mode) -> Result<String, (usize, EscapeError)> {
+    let bytes = input.as_bytes();
+    let mut v = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            let next = find_next_backslash(&bytes[i..])
+                .map(|pos| i + pos)
+                .unwrap_or(bytes.len());
+            v.extend_from_slice(&bytes[i..next]);
+            i = next;
+            continue;
+        }
+
+        let escape_start = i;
+        match bytes.get(i + 1) {
+            None => {
+                // A lone trailing backslash has nothing to escape, so it is passed through as-is.
+                v.push(bytes[i]);
+                i += 1;
+            }
+            Some(&c) if is_simple_escape(c as char) => {
+                v.push(char_to_escape_sequence(c as char) as u8);
+                i += 2;
+            }
+            Some(&c) if is_complex_escape(c as char) => {
+                match c as char {
+                    'x' => {
+                        let (byte, consumed) = parse_hex_escape(bytes, i + 2, mode)
+                            .map_err(|e| (escape_start, e))?;
+                        // A `\xNN` escape decodes to the Unicode scalar U+00NN, not the raw byte
+                        // `NN` — in Byte mode that scalar can be above U+007F (e.g. `\xff` is
+                        // U+00FF `ÿ`), which only fits in the output as its multi-byte UTF-8
+                        // encoding, not as a lone non-UTF-8 byte.
+                        let mut buf = [0u8; 4];
+                        v.extend_from_slice((byte as char).encode_utf8(&mut buf).as_bytes());
+                        i += 2 + consumed;
+                    }
+                    'u' if mode.allows_unicode_escape() => {
+                        let (chr, consumed) = parse_unicode_escape(bytes, i + 2)
+                            .map_err(|e| (escape_start, e))?;
+                        let mut buf = [0u8; 4];
+                        v.extend_from_slice(chr.encode_utf8(&mut buf).as_bytes());
+                        i += 2 + consumed;
+                    }
+                    'u' => return Err((escape_start, EscapeError::UnicodeEscapeInByte)),
+                    _ => unreachable!("is_complex_escape only matches 'x' and 'u'"),
+                }
+            }
+            Some(_) => return Err((escape_start, EscapeError::InvalidEscape)),
+        }
+    }
+    String::from_utf8(v).map_err(|e| {
+        let offset = e.utf8_error().valid_up_to();
+        (offset, EscapeError::NonUtf8(e))
+    })
+}
+
+/// Parses a `NN` hex pair starting at `bytes[i]` (the byte right after `\x`).
+///
+/// Returns the decoded byte and the number of bytes consumed from `bytes[i]` (always 2 on
+/// success), or an error if the input ends too soon, a digit isn't hex, or the value exceeds
+/// `mode`'s maximum.
+fn parse_hex_escape(bytes: &[u8], i: usize, mode: Mode) -> Result<(u8, usize), EscapeError> {
+    let hi = *bytes.get(i).ok_or(EscapeError::TooShortHexEscape)?;
+    let lo = *bytes.get(i + 1).ok_or(EscapeError::TooShortHexEscape)?;
+    let hi = ascii_to_hex(hi).ok_or(EscapeError::InvalidCharInHexEscape)?;
+    let lo = ascii_to_hex(lo).ok_or(EscapeError::InvalidCharInHexEscape)?;
+    let value = hi * 16 + lo;
+    if value > mode.max_hex_escape() {
+        return Err(EscapeError::OutOfRangeHexEscape);
+    }
+    Ok((value, 2))
+}
+
+/// Parses a `{...}` unicode escape body starting at `bytes[i]` (the byte right after `\u`).
+///
+/// Returns the decoded `char` and the number of bytes consumed from `bytes[i]`, or an error if
+/// the escape is missing its brace, empty, unterminated, contains an invalid hex digit, or
+/// encodes a code point that is out of range or a surrogate.
+fn parse_unicode_escape(bytes: &[u8], i: usize) -> Result<(char, usize), EscapeError> {
+    let start = i;
+    let mut i = i;
+    if bytes.get(i) != Some(&b'{') {
+        return Err(EscapeError::NoBraceInUnicodeEscape);
+    }
+    i += 1;
+
+    let mut value: u32 = 0;
+    let mut digits = 0;
+    loop {
+        match bytes.get(i) {
+            Some(b'_') => i += 1,
+            Some(b'}') => break,
+            Some(&b) => {
+                if digits >= 6 {
+                    return Err(EscapeError::OverlongUnicodeEscape);
+                }
+                value = value * 16 + ascii_to_hex(b).ok_or(EscapeError::InvalidCharInHexEscape)? as u32;
+                digits += 1;
+                i += 1;
+            }
+            None => return Err(EscapeError::UnclosedUnicodeEscape),
+        }
+    }
+    if digits == 0 {
+        return Err(EscapeError::EmptyUnicodeEscape);
+    }
+    i += 1; // consume the closing '}'
+
+    if (0xD800..=0xDFFF).contains(&value) {
+        return Err(EscapeError::LoneSurrogateUnicodeEscape);
+    }
+    if value > 0x10FFFF {
+        return Err(EscapeError::OutOfRangeUnicodeEscape);
+    }
+    let chr = char::from_u32(value).ok_or(EscapeError::OutOfRangeUnicodeEscape)?;
+    Ok((chr, i - start))
 }
 
 /// Escape [Quote escapes](https://doc.rust-lang.org/reference/tokens.html#quote-escapes) in
 /// `input`
-pub fn escape_quotes(_input: &str) -> Result<String, std::string::FromUtf8Error> {
+pub fn escape_quotes(_input: &str) -> Result<String, (usize, EscapeError)> {
     unimplemented!("`escape_quotes` is not yet implemented");
 }
 
+/// Decode the escapes in `input` without allocating a `String`.
+///
+/// Scans `input` once, invoking `callback` with the half-open byte range each decoded unit
+/// occupied in `input` and either the `char` it decoded to or the error that prevented it from
+/// decoding. Plain characters are reported too, so a caller can reconstruct the full output, or
+/// just collect ranges and errors to validate a literal without ever materializing a string.
+///
+/// Unlike [`escape_ascii`]/[`escape_bytes`]/[`escape_unicode`], scanning does not stop at the
+/// first error: recovery resumes after the bad escape so the callback can observe every error in
+/// `input`, the way rustc's lexer validates a whole literal in one pass.
+pub fn unescape_str(
+    input: &str,
+    mode: Mode,
+    callback: &mut impl FnMut(Range<usize>, Result<char, EscapeError>),
+) {
+    let bytes = input.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            let next = find_next_backslash(&bytes[i..])
+                .map(|pos| i + pos)
+                .unwrap_or(bytes.len());
+            for (offset, c) in input[i..next].char_indices() {
+                let start = i + offset;
+                callback(start..start + c.len_utf8(), Ok(c));
+            }
+            i = next;
+            continue;
+        }
+
+        let escape_start = i;
+        match bytes.get(i + 1) {
+            None => {
+                callback(escape_start..escape_start + 1, Ok('\\'));
+                i += 1;
+            }
+            Some(&b) if is_simple_escape(b as char) => {
+                callback(
+                    escape_start..escape_start + 2,
+                    Ok(char_to_escape_sequence(b as char)),
+                );
+                i += 2;
+            }
+            Some(&b) if is_complex_escape(b as char) => match b as char {
+                'x' => match parse_hex_escape(bytes, i + 2, mode) {
+                    Ok((byte, consumed)) => {
+                        // `\xNN` decodes to the Unicode scalar U+00NN, matching escape_with's
+                        // convention for the same escape (Byte mode's `0x80..=0xFF` range is a
+                        // scalar there too, not a raw byte — the output can't hold a lone
+                        // non-UTF-8 byte either way).
+                        callback(escape_start..escape_start + 2 + consumed, Ok(byte as char));
+                        i = escape_start + 2 + consumed;
+                    }
+                    Err(e) => {
+                        let end = (escape_start + 2).min(bytes.len());
+                        callback(escape_start..end, Err(e));
+                        i = end;
+                    }
+                },
+                'u' if mode.allows_unicode_escape() => match parse_unicode_escape(bytes, i + 2) {
+                    Ok((chr, consumed)) => {
+                        callback(escape_start..escape_start + 2 + consumed, Ok(chr));
+                        i = escape_start + 2 + consumed;
+                    }
+                    Err(e) => {
+                        let end = (escape_start + 2).min(bytes.len());
+                        callback(escape_start..end, Err(e));
+                        i = end;
+                    }
+                },
+                'u' => {
+                    let end = (escape_start + 2).min(bytes.len());
+                    callback(escape_start..end, Err(EscapeError::UnicodeEscapeInByte));
+                    i = end;
+                }
+                _ => unreachable!("is_complex_escape only matches 'x' and 'u'"),
+            },
+            Some(_) => {
+                let end = (escape_start + 2).min(bytes.len());
+                callback(escape_start..end, Err(EscapeError::InvalidEscape));
+                i = end;
+            }
+        }
+    }
+}
+
+/// Serialize `input` into backslash-escaped form, the inverse of [`escape_ascii`],
+/// [`escape_bytes`], and [`escape_unicode`].
+///
+/// Printable ASCII passes through untouched. `\n`, `\t`, `\r`, `\\`, and `\0` are written out as
+/// their short escapes. Other bytes below `0x20`, and (in [`Mode::Byte`]) bytes `0x7F..=0xFF`,
+/// are written as `\xNN`. Everything else is written as `\u{...}` when `mode` allows unicode
+/// escapes, or passed through raw otherwise. The result is always legal to feed back into the
+/// matching decoder, i.e. `escape_ascii(&to_escaped(s, Mode::Ascii)).unwrap() == s`.
+pub fn to_escaped(input: &str, mode: Mode) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '\n' => out.push_str(r"\n"),
+            '\t' => out.push_str(r"\t"),
+            '\r' => out.push_str(r"\r"),
+            '\\' => out.push_str(r"\\"),
+            '\0' => out.push_str(r"\0"),
+            ' ' => out.push(' '),
+            c if c.is_ascii_graphic() => out.push(c),
+            c if (c as u32) < 0x20 => out.push_str(&format!(r"\x{:02x}", c as u32)),
+            c if mode == Mode::Byte && (c as u32) <= 0xFF => {
+                out.push_str(&format!(r"\x{:02x}", c as u32))
+            }
+            c if mode.allows_unicode_escape() => out.push_str(&format!(r"\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 fn char_to_escape_sequence(chr: char) -> char {
     match chr {
         'n' => '\n',
@@ -56,39 +360,105 @@ fn char_to_escape_sequence(chr: char) -> char {
 }
 
 fn is_simple_escape(chr: char) -> bool {
-    match chr {
-        'n' | 't' | 'r' | '\\' | '0' => true,
-        _ => false,
-    }
+    matches!(chr, 'n' | 't' | 'r' | '\\' | '0')
 }
 
 fn is_complex_escape(chr: char) -> bool {
-    match chr {
-        'x' | 'u' => true,
-        _ => false,
-    }
+    matches!(chr, 'x' | 'u')
 }
 
-fn ascii_to_hex(x: u8) -> u8 {
+fn ascii_to_hex(x: u8) -> Option<u8> {
     match x as char {
-        '0' => 0,
-        '1' => 1,
-        '2' => 2,
-        '3' => 3,
-        '4' => 4,
-        '5' => 5,
-        '6' => 6,
-        '7' => 7,
-        '8' => 8,
-        '9' => 9,
-        'a' | 'A' => 10,
-        'b' | 'B' => 11,
-        'c' | 'C' => 12,
-        'd' | 'D' => 13,
-        'e' | 'E' => 14,
-        'f' | 'F' => 15,
-        _ => panic!("expected hex value"),
+        '0' => Some(0),
+        '1' => Some(1),
+        '2' => Some(2),
+        '3' => Some(3),
+        '4' => Some(4),
+        '5' => Some(5),
+        '6' => Some(6),
+        '7' => Some(7),
+        '8' => Some(8),
+        '9' => Some(9),
+        'a' | 'A' => Some(10),
+        'b' | 'B' => Some(11),
+        'c' | 'C' => Some(12),
+        'd' | 'D' => Some(13),
+        'e' | 'E' => Some(14),
+        'f' | 'F' => Some(15),
+        _ => None,
+    }
+}
+
+/// 256-entry table classifying each byte as "copy verbatim" (`false`) or "needs handling"
+/// (`true`). Only the backslash itself needs handling; every other byte, including non-ASCII
+/// UTF-8 continuation bytes, can be bulk-copied straight into the output.
+const NEEDS_ESCAPE_HANDLING: [bool; 256] = {
+    let mut table = [false; 256];
+    table[b'\\' as usize] = true;
+    table
+};
+
+/// Finds the byte offset of the next byte in `bytes` that needs escape handling (currently just
+/// `\\`), preferring a SIMD-accelerated scan over an AVX2 or SSE2 lookup-table compare with a
+/// scalar fallback, selected at runtime so the same binary runs on older CPUs.
+///
+/// Most real input is escape-free or escape-sparse, so this keeps that common case close to
+/// `memcpy` speed instead of paying a branch per byte.
+fn find_next_backslash(bytes: &[u8]) -> Option<usize> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { find_next_backslash_avx2(bytes) };
+        }
+        if is_x86_feature_detected!("sse2") {
+            return unsafe { find_next_backslash_sse2(bytes) };
+        }
     }
+    find_next_backslash_scalar(bytes)
+}
+
+fn find_next_backslash_scalar(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().position(|&b| NEEDS_ESCAPE_HANDLING[b as usize])
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn find_next_backslash_avx2(bytes: &[u8]) -> Option<usize> {
+    use std::arch::x86_64::{
+        _mm256_cmpeq_epi8, _mm256_loadu_si256, _mm256_movemask_epi8, _mm256_set1_epi8,
+    };
+
+    let needle = _mm256_set1_epi8(b'\\' as i8);
+    let mut i = 0;
+    while i + 32 <= bytes.len() {
+        let chunk = _mm256_loadu_si256(bytes.as_ptr().add(i) as *const _);
+        let eq = _mm256_cmpeq_epi8(chunk, needle);
+        let mask = _mm256_movemask_epi8(eq) as u32;
+        if mask != 0 {
+            return Some(i + mask.trailing_zeros() as usize);
+        }
+        i += 32;
+    }
+    find_next_backslash_scalar(&bytes[i..]).map(|pos| i + pos)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn find_next_backslash_sse2(bytes: &[u8]) -> Option<usize> {
+    use std::arch::x86_64::{_mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8};
+
+    let needle = _mm_set1_epi8(b'\\' as i8);
+    let mut i = 0;
+    while i + 16 <= bytes.len() {
+        let chunk = _mm_loadu_si128(bytes.as_ptr().add(i) as *const _);
+        let eq = _mm_cmpeq_epi8(chunk, needle);
+        let mask = _mm_movemask_epi8(eq) as u32;
+        if mask != 0 {
+            return Some(i + mask.trailing_zeros() as usize);
+        }
+        i += 16;
+    }
+    find_next_backslash_scalar(&bytes[i..]).map(|pos| i + pos)
 }
 
 #[cfg(test)]
@@ -294,4 +664,142 @@ mod tests {
             assert!(is_simple_escape('0'));
         }
     }
+
+    mod test_unescape_str {
+        use super::*;
+
+        #[test]
+        fn test_plain_chars_reported() {
+            let mut seen = Vec::new();
+            unescape_str("ab", Mode::Ascii, &mut |range, result| {
+                seen.push((range, result));
+            });
+            assert_eq!(seen, vec![(0..1, Ok('a')), (1..2, Ok('b')),]);
+        }
+
+        #[test]
+        fn test_simple_escape_range() {
+            let mut seen = Vec::new();
+            unescape_str(r#"\n"#, Mode::Ascii, &mut |range, result| {
+                seen.push((range, result));
+            });
+            assert_eq!(seen, vec![(0..2, Ok('\n'))]);
+        }
+
+        #[test]
+        fn test_hex_escape_range() {
+            let mut seen = Vec::new();
+            unescape_str(r#"\x41"#, Mode::Ascii, &mut |range, result| {
+                seen.push((range, result));
+            });
+            assert_eq!(seen, vec![(0..4, Ok('A'))]);
+        }
+
+        #[test]
+        fn test_byte_mode_high_hex_escape_agrees_with_escape_bytes() {
+            let mut seen = Vec::new();
+            unescape_str(r#"\xff"#, Mode::Byte, &mut |range, result| {
+                seen.push((range, result));
+            });
+            assert_eq!(seen, vec![(0..4, Ok('\u{ff}'))]);
+            assert_eq!('\u{ff}'.to_string(), escape_bytes(r#"\xff"#).unwrap());
+        }
+
+        #[test]
+        fn test_unicode_escape_range() {
+            let mut seen = Vec::new();
+            unescape_str(r#"\u{1f980}"#, Mode::Unicode, &mut |range, result| {
+                seen.push((range, result));
+            });
+            assert_eq!(seen, vec![(0..9, Ok('🦀'))]);
+        }
+
+        #[test]
+        fn test_multiple_errors_are_all_reported() {
+            let mut errors = Vec::new();
+            unescape_str(r#"\z\q"#, Mode::Ascii, &mut |range, result| {
+                if let Err(e) = result {
+                    errors.push((range, e));
+                }
+            });
+            assert_eq!(
+                errors,
+                vec![
+                    (0..2, EscapeError::InvalidEscape),
+                    (2..4, EscapeError::InvalidEscape),
+                ]
+            );
+        }
+    }
+
+    mod test_to_escaped {
+        use super::*;
+
+        #[test]
+        fn test_printable_passes_through() {
+            assert_eq!("hello world", to_escaped("hello world", Mode::Ascii));
+        }
+
+        #[test]
+        fn test_simple_escapes_round_trip() {
+            let s = "hello\nworld\t\r\\\0";
+            assert_eq!(s, escape_ascii(&to_escaped(s, Mode::Ascii)).unwrap());
+        }
+
+        #[test]
+        fn test_ascii_control_byte_round_trips() {
+            let s = "hello\x01world";
+            assert_eq!(s, escape_ascii(&to_escaped(s, Mode::Ascii)).unwrap());
+        }
+
+        #[test]
+        fn test_byte_mode_high_byte_round_trips() {
+            let s = "hello\u{ff}world";
+            assert_eq!(s, escape_bytes(&to_escaped(s, Mode::Byte)).unwrap());
+        }
+
+        #[test]
+        fn test_byte_mode_full_high_byte_range_round_trips() {
+            for byte in 0x80u32..=0xFF {
+                let s = char::from_u32(byte).unwrap().to_string();
+                assert_eq!(s, escape_bytes(&to_escaped(&s, Mode::Byte)).unwrap());
+            }
+        }
+
+        #[test]
+        fn test_unicode_mode_crab_emoji_round_trips() {
+            let s = "Hello🦀world";
+            assert_eq!(s, escape_unicode(&to_escaped(s, Mode::Unicode)).unwrap());
+        }
+    }
+
+    mod test_find_next_backslash {
+        use super::*;
+
+        #[test]
+        fn test_no_backslash() {
+            assert_eq!(None, find_next_backslash(b"hello world"));
+        }
+
+        #[test]
+        fn test_backslash_in_middle() {
+            assert_eq!(Some(5), find_next_backslash(b"hello\\world"));
+        }
+
+        #[test]
+        fn test_backslash_past_one_simd_lane() {
+            let input = [b'a'; 40];
+            assert_eq!(None, find_next_backslash(&input));
+
+            let mut with_backslash = input;
+            with_backslash[33] = b'\\';
+            assert_eq!(Some(33), find_next_backslash(&with_backslash));
+        }
+
+        #[test]
+        fn test_escape_ascii_on_long_escape_free_input() {
+            let input = "x".repeat(100);
+            assert_eq!(input, escape_ascii(&input).unwrap());
+        }
+    }
 }